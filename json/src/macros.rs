@@ -51,166 +51,343 @@
 /// ]);
 /// # }
 /// ```
+///
+/// If an interpolated expression already is a `serde_json::Value`, prefix it
+/// with `@(...)` to move it into the literal directly instead of paying for a
+/// round trip through `to_value`.
+///
+/// ```rust
+/// # #![allow(unused_variables)]
+/// # #[macro_use] extern crate serde_json;
+/// # fn main() {
+/// let inner = json!({ "nested": true });
+///
+/// let value = json!({
+///     "cached": @(inner)
+/// });
+/// # }
+/// ```
+///
+/// An object literal can spread the entries of an existing object with
+/// `..expr`, JavaScript-object-spread style. Later entries, whether from a
+/// spread or written out literally, overwrite earlier ones with the same
+/// key. The spread expression can be a `Value` (the common case — merging
+/// in a previously-built document) or anything else `Into<Value>`, such as
+/// a `Map<String, Value>`; it panics at runtime if the value it produces
+/// isn't a `Value::Object`.
+///
+/// ```rust
+/// # #![allow(unused_variables)]
+/// # #[macro_use] extern crate serde_json;
+/// # fn main() {
+/// let base = json!({ "code": 200, "success": true });
+///
+/// let value = json!({
+///     ..base.clone(),
+///     "success": false,
+/// });
+/// # }
+/// ```
+///
+/// A key computed from an arbitrary expression can be written in
+/// parentheses, disambiguating it from a bare identifier or string literal
+/// key.
+///
+/// ```rust
+/// # #![allow(unused_variables)]
+/// # #[macro_use] extern crate serde_json;
+/// # fn main() {
+/// let i = 0;
+///
+/// let value = json!({
+///     (format!("item_{}", i)): "first"
+/// });
+/// # }
+/// ```
 #[macro_export]
 macro_rules! json {
     // Hide distracting implementation details from the generated rustdoc.
     ($($json:tt)+) => {
-        json_internal!($($json)+)
+        $crate::json_internal!(infallible $($json)+)
     };
 }
 
+/// Construct a `serde_json::Value` from a JSON literal, without panicking on
+/// a serialization failure.
+///
+/// This is the fallible counterpart to [`json!`](macro.json.html). Every tree
+/// shape (`null`, `true`, `false`, arrays, objects) is infallible to build,
+/// but interpolating an expression runs it through `Serialize`, which can
+/// fail — a map with non-string keys, or a type with a custom `Serialize`
+/// impl that returns an error. `json!` unwraps that failure into a panic;
+/// `try_json!` threads it through `?` instead, so the whole literal
+/// evaluates to `Result<Value, serde_json::Error>` and the first failure
+/// short-circuits the rest. It supports every other `json!` feature — `@(...)`
+/// moves, object spreads, parenthesized keys — since both macros share the
+/// same TT muncher below. A spread (`..expr`) whose value is not an object
+/// still panics, exactly as it does in `json!`; it is not turned into an
+/// `Err`.
+///
+/// ```rust
+/// # #[macro_use] extern crate serde_json;
+/// # fn main() {
+/// let code = 200;
+/// let value = try_json!({
+///     "code": code,
+///     "success": code == 200,
+/// });
+/// assert_eq!(value.unwrap()["code"], 200);
+/// # }
+/// ```
 #[macro_export]
-#[doc(hidden)]
-macro_rules! json_internal {
-    (null) => {
-        $crate::Value::Null
-    };
-
-    (true) => {
-        $crate::Value::Bool(true)
-    };
-
-    (false) => {
-        $crate::Value::Bool(false)
-    };
-
-    ([]) => {
-        $crate::Value::Array(vec![])
-    };
-
-    ([ $($tt:tt)+ ]) => {
-        $crate::Value::Array(json_within_array!([] $($tt)+))
-    };
-
-    ({}) => {
-        $crate::Value::Object($crate::Map::new())
-    };
-
-    ({ $($tt:tt)+ }) => {
-        $crate::Value::Object({
-            let mut object = $crate::Map::new();
-            json_within_object!(object () () $($tt)+);
-            object
-        })
-    };
-
-    // Any Serialize type: numbers, strings, struct literals, variables etc.
-    ($other:expr) => {
-        $crate::to_value(&$other).unwrap()
+macro_rules! try_json {
+    ($($json:tt)+) => {
+        (|| -> $crate::Result<$crate::Value> {
+            Ok($crate::json_internal!(fallible $($json)+))
+        })()
     };
 }
 
-// TT muncher for parsing the inside of an array [...]. Produces a vec![...] of
-// the elements.
-//
-// Must be invoked as: json_within_array!([] $($tt)*)
+// Rocket-propelled, `$crate`-qualified TT muncher shared by both `json!`
+// (`$mode` = `infallible`) and `try_json!` (`$mode` = `fallible`). Every
+// recursive step names itself via `$crate::json_internal!` and re-threads
+// `$mode` unchanged, so the macro keeps working when this crate is imported
+// under a renamed dependency and `try_json!` can never drift out of sync with
+// whatever tree shapes `json!` supports — there's exactly one muncher to
+// extend. The `@array` and `@object` arms are the two parsing phases that
+// used to live in their own `macro_export`ed macros; folding them in here
+// means those names no longer leak into a caller's macro namespace. Only the
+// final `$other:expr` leaf differs between the two modes: `infallible`
+// unwraps, `fallible` short-circuits with `?`.
 #[macro_export]
 #[doc(hidden)]
-macro_rules! json_within_array {
+macro_rules! json_internal {
+    //////////////////////////////////////////////////////////////////////
+    // TT muncher for parsing the inside of an array [...]. Produces a
+    // vec![...] of the elements.
+    //
+    // Must be invoked as: json_internal!(@array $mode [] $($tt)*)
+    //////////////////////////////////////////////////////////////////////
+
     // Done with trailing comma.
-    ([$($elems:expr,)*]) => {
+    (@array $mode:ident [$($elems:expr,)*]) => {
         vec![$($elems,)*]
     };
 
     // Done without trailing comma.
-    ([$($elems:expr),*]) => {
+    (@array $mode:ident [$($elems:expr),*]) => {
         vec![$($elems),*]
     };
 
     // Next element is `null`.
-    ([$($elems:expr,)*] null $($rest:tt)*) => {
-        json_within_array!([$($elems,)* json!(null)] $($rest)*)
+    (@array $mode:ident [$($elems:expr,)*] null $($rest:tt)*) => {
+        $crate::json_internal!(@array $mode [$($elems,)* $crate::json_internal!($mode null)] $($rest)*)
     };
 
     // Next element is `true`.
-    ([$($elems:expr,)*] true $($rest:tt)*) => {
-        json_within_array!([$($elems,)* json!(true)] $($rest)*)
+    (@array $mode:ident [$($elems:expr,)*] true $($rest:tt)*) => {
+        $crate::json_internal!(@array $mode [$($elems,)* $crate::json_internal!($mode true)] $($rest)*)
     };
 
     // Next element is `false`.
-    ([$($elems:expr,)*] false $($rest:tt)*) => {
-        json_within_array!([$($elems,)* json!(false)] $($rest)*)
+    (@array $mode:ident [$($elems:expr,)*] false $($rest:tt)*) => {
+        $crate::json_internal!(@array $mode [$($elems,)* $crate::json_internal!($mode false)] $($rest)*)
     };
 
     // Next element is an array.
-    ([$($elems:expr,)*] [$($array:tt)*] $($rest:tt)*) => {
-        json_within_array!([$($elems,)* json!([$($array)*])] $($rest)*)
+    (@array $mode:ident [$($elems:expr,)*] [$($array:tt)*] $($rest:tt)*) => {
+        $crate::json_internal!(@array $mode [$($elems,)* $crate::json_internal!($mode [$($array)*])] $($rest)*)
     };
 
     // Next element is a map.
-    ([$($elems:expr,)*] {$($map:tt)*} $($rest:tt)*) => {
-        json_within_array!([$($elems,)* json!({$($map)*})] $($rest)*)
+    (@array $mode:ident [$($elems:expr,)*] {$($map:tt)*} $($rest:tt)*) => {
+        $crate::json_internal!(@array $mode [$($elems,)* $crate::json_internal!($mode {$($map)*})] $($rest)*)
+    };
+
+    // Next element is an already-built Value, moved in without a
+    // to_value/from_value round trip. Placed before the generic
+    // `$next:expr` arm below: `@` is not a valid start of an expr, so
+    // `$next:expr` can never match it anyway, but keeping the more specific
+    // arm first documents the precedence.
+    (@array $mode:ident [$($elems:expr,)*] @($val:expr), $($rest:tt)*) => {
+        $crate::json_internal!(@array $mode [$($elems,)* $crate::json_internal!($mode @($val))] $($rest)*)
+    };
+
+    (@array $mode:ident [$($elems:expr,)*] @($val:expr)) => {
+        $crate::json_internal!(@array $mode [$($elems,)* $crate::json_internal!($mode @($val))])
     };
 
     // Next element is an expression followed by comma.
-    ([$($elems:expr,)*] $next:expr, $($rest:tt)*) => {
-        json_within_array!([$($elems,)* json!($next),] $($rest)*)
+    (@array $mode:ident [$($elems:expr,)*] $next:expr, $($rest:tt)*) => {
+        $crate::json_internal!(@array $mode [$($elems,)* $crate::json_internal!($mode $next),] $($rest)*)
     };
 
     // Last element is an expression with no trailing comma.
-    ([$($elems:expr,)*] $last:expr) => {
-        json_within_array!([$($elems,)* json!($last)])
+    (@array $mode:ident [$($elems:expr,)*] $last:expr) => {
+        $crate::json_internal!(@array $mode [$($elems,)* $crate::json_internal!($mode $last)])
     };
 
     // Comma after the most recent element.
-    ([$($elems:expr),*] , $($rest:tt)*) => {
-        json_within_array!([$($elems,)*] $($rest)*)
+    (@array $mode:ident [$($elems:expr),*] , $($rest:tt)*) => {
+        $crate::json_internal!(@array $mode [$($elems,)*] $($rest)*)
     };
-}
 
-// TT muncher for parsing the inside of an object {...}. Each entry is inserted
-// into the given map variable.
-//
-// Must be invoked as: json_within_object!(var () () $($tt)*)
-#[macro_export]
-#[doc(hidden)]
-macro_rules! json_within_object {
+    //////////////////////////////////////////////////////////////////////
+    // TT muncher for parsing the inside of an object {...}. Each entry is
+    // inserted into the given map variable.
+    //
+    // Must be invoked as: json_internal!(@object $mode $obj () () $($tt)*)
+    //////////////////////////////////////////////////////////////////////
+
     // Done.
-    ($object:ident () ()) => {};
+    (@object $mode:ident $object:ident () ()) => {};
 
     // Insert a single entry. The key and value must both be more than zero
     // tokens. The key must be Into-convertible to String.
-    ($object:ident ($($key:tt)+) : ($($value:tt)+)) => {
-        $object.insert(($($key)+).into(), json!($($value)+));
+    (@object $mode:ident $object:ident ($($key:tt)+) : ($($value:tt)+)) => {
+        $object.insert(($($key)+).into(), $crate::json_internal!($mode $($value)+));
+    };
+
+    // Spread: `..$spread` merges every key/value pair of an existing object
+    // into this one, JS-object-spread style. Must come before the generic
+    // key-munching arm below, and only fires with an empty key accumulator
+    // so `..` can't appear in the middle of a key. Spreading never returns an
+    // error (a non-object panics in both modes), so this doesn't branch on
+    // `$mode`.
+    (@object $mode:ident $object:ident () () .. $spread:expr, $($rest:tt)*) => {
+        $crate::json_internal!(@object_spread $object $spread);
+        $crate::json_internal!(@object $mode $object () () $($rest)*);
     };
 
-    // Misplaced colon. Trigger a reasonable error message by failing to match
-    // the colon in the recursive call.
-    ($object:ident () () : $($rest:tt)*) => {
-        json_within_object!($object :);
+    (@object $mode:ident $object:ident () () .. $spread:expr) => {
+        $crate::json_internal!(@object_spread $object $spread);
     };
 
-    // Found a comma inside a key. Trigger a reasonable error message by failing
-    // to match the comma in the recursive call.
-    ($object:ident ($($key:tt)*) () , $($rest:tt)*) => {
-        json_within_object!($object ,);
+    // Parenthesized computed key: `(expr): value`. This disambiguates a
+    // complex key expression from the bare-identifier/string-literal key
+    // path, which munches one token at a time and can get confused by
+    // commas or colons nested inside the key expression. Must come before
+    // the generic key-munching arm below.
+    (@object $mode:ident $object:ident () () ($key:expr) : $($rest:tt)*) => {
+        $crate::json_internal!(@object $mode $object ($key) : () $($rest)*);
+    };
+
+    // Misplaced colon. Trigger a reasonable error message by failing to
+    // match the colon in the recursive call.
+    (@object $mode:ident $object:ident () () : $($rest:tt)*) => {
+        $crate::json_internal!(@object $mode $object :);
+    };
+
+    // Found a comma inside a key. Trigger a reasonable error message by
+    // failing to match the comma in the recursive call.
+    (@object $mode:ident $object:ident ($($key:tt)*) () , $($rest:tt)*) => {
+        $crate::json_internal!(@object $mode $object ,);
     };
 
     // Found a colon after a key. Move on to the value.
-    ($object:ident ($($key:tt)+) () : $($rest:tt)*) => {
-        json_within_object!($object ($($key)+) : () $($rest)*);
+    (@object $mode:ident $object:ident ($($key:tt)+) () : $($rest:tt)*) => {
+        $crate::json_internal!(@object $mode $object ($($key)+) : () $($rest)*);
     };
 
-    // Misplaced comma. Trigger a reasonable error message by failing to match
-    // the comma in the recursive call.
-    ($object:ident ($($key:tt)+) : () , $($rest:tt)*) => {
-        json_within_object!($object ,);
+    // Misplaced comma. Trigger a reasonable error message by failing to
+    // match the comma in the recursive call.
+    (@object $mode:ident $object:ident ($($key:tt)+) : () , $($rest:tt)*) => {
+        $crate::json_internal!(@object $mode $object ,);
     };
 
-    // Found a comma after a value. Insert whatever we have so far and move on
-    // to remaining elements. Trailing comma is allowed.
-    ($object:ident ($($key:tt)+) : ($($value:tt)+) , $($rest:tt)*) => {
-        json_within_object!($object ($($key)+) : ($($value)+));
-        json_within_object!($object () () $($rest)*);
+    // Found a comma after a value. Insert whatever we have so far and move
+    // on to remaining elements. Trailing comma is allowed.
+    (@object $mode:ident $object:ident ($($key:tt)+) : ($($value:tt)+) , $($rest:tt)*) => {
+        $crate::json_internal!(@object $mode $object ($($key)+) : ($($value)+));
+        $crate::json_internal!(@object $mode $object () () $($rest)*);
     };
 
     // Munch a token into the current key.
-    ($object:ident ($($key:tt)*) () $tt:tt $($rest:tt)*) => {
-        json_within_object!($object ($($key)* $tt) () $($rest)*)
+    (@object $mode:ident $object:ident ($($key:tt)*) () $tt:tt $($rest:tt)*) => {
+        $crate::json_internal!(@object $mode $object ($($key)* $tt) () $($rest)*)
     };
 
     // Munch a token into the current value.
-    ($object:ident ($($key:tt)+) : ($($value:tt)*) $tt:tt $($rest:tt)*) => {
-        json_within_object!($object ($($key)+) : ($($value)* $tt) $($rest)*)
+    (@object $mode:ident $object:ident ($($key:tt)+) : ($($value:tt)*) $tt:tt $($rest:tt)*) => {
+        $crate::json_internal!(@object $mode $object ($($key)+) : ($($value)* $tt) $($rest)*)
+    };
+
+    // Merge every key/value pair of `$spread` into `$object`, later entries
+    // overwriting earlier ones since the map insert just runs in order.
+    // `$spread` is moved through `Value::from` rather than `to_value` so an
+    // already-built `Value::Object` (the common case) isn't re-serialized —
+    // `Into<Value>` also covers a bare `Map` directly. Spreading anything
+    // else is almost always a bug at the call site, so we panic with the
+    // offending expression rather than silently contributing no entries.
+    // Doesn't take `$mode`: the panic above fires the same way under
+    // `try_json!`, and there is no other failure to turn into an `Err`.
+    (@object_spread $object:ident $spread:expr) => {
+        match $crate::Value::from($spread) {
+            $crate::Value::Object(map) => {
+                for (key, value) in map {
+                    $object.insert(key, value);
+                }
+            }
+            other => panic!(
+                "object spread `..{}` in json! did not evaluate to a JSON object, got {:?}",
+                stringify!($spread),
+                other,
+            ),
+        }
+    };
+
+    //////////////////////////////////////////////////////////////////////
+    // The main entry points. `$mode` is `infallible` for `json!` or
+    // `fallible` for `try_json!`; every arm below ignores its value and just
+    // threads it through — except the final `$other:expr` leaf, which is
+    // the only place the two modes actually differ.
+    //////////////////////////////////////////////////////////////////////
+
+    ($mode:ident null) => {
+        $crate::Value::Null
+    };
+
+    ($mode:ident true) => {
+        $crate::Value::Bool(true)
+    };
+
+    ($mode:ident false) => {
+        $crate::Value::Bool(false)
+    };
+
+    ($mode:ident []) => {
+        $crate::Value::Array(vec![])
+    };
+
+    ($mode:ident [ $($tt:tt)+ ]) => {
+        $crate::Value::Array($crate::json_internal!(@array $mode [] $($tt)+))
+    };
+
+    ($mode:ident {}) => {
+        $crate::Value::Object($crate::Map::new())
+    };
+
+    ($mode:ident { $($tt:tt)+ }) => {
+        $crate::Value::Object({
+            let mut object = $crate::Map::new();
+            $crate::json_internal!(@object $mode object () () $($tt)+);
+            object
+        })
+    };
+
+    // `@(expr)` is the escape hatch for an expression that is already a
+    // Value (or anything `Into<Value>`): move it in directly instead of
+    // serializing and deserializing it again.
+    ($mode:ident @($val:expr)) => {
+        $crate::Value::from($val)
+    };
+
+    // Any Serialize type: numbers, strings, struct literals, variables etc.
+    (infallible $other:expr) => {
+        $crate::to_value(&$other).unwrap()
+    };
+
+    (fallible $other:expr) => {
+        $crate::to_value(&$other)?
     };
 }